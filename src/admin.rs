@@ -0,0 +1,111 @@
+use axum::extract::State;
+use axum::http::{HeaderMap, StatusCode};
+use axum::Json;
+use sage_core::database::Builder;
+use serde::Serialize;
+use std::sync::Arc;
+use subtle::ConstantTimeEq;
+
+use crate::AppState;
+
+#[derive(Serialize)]
+pub struct DatabaseSummary {
+    peptides: usize,
+    fragments: usize,
+}
+
+fn check_api_key(
+    headers: &HeaderMap,
+    configured: &Option<String>,
+) -> Result<(), (StatusCode, String)> {
+    let configured = configured
+        .as_deref()
+        .ok_or((StatusCode::NOT_FOUND, "admin API is disabled".into()))?;
+
+    let provided = headers
+        .get("x-api-key")
+        .and_then(|value| value.to_str().ok());
+
+    let matches = provided
+        .map(|provided| provided.as_bytes().ct_eq(configured.as_bytes()).into())
+        .unwrap_or(false);
+
+    if !matches {
+        return Err((StatusCode::UNAUTHORIZED, "invalid or missing x-api-key".into()));
+    }
+
+    Ok(())
+}
+
+/// `POST /admin/reload` — rebuild the `IndexedDatabase` and atomically swap
+/// it in via `ArcSwap`.
+pub async fn reload_v1(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(builder): Json<Builder>,
+) -> Result<Json<DatabaseSummary>, (StatusCode, String)> {
+    check_api_key(&headers, &state.config.admin_api_key)?;
+
+    let parameters = builder.make_parameters();
+    let contents = tokio::fs::read_to_string(&parameters.fasta)
+        .await
+        .map_err(|e| (StatusCode::BAD_REQUEST, format!("failed to read fasta: {e}")))?;
+
+    let db = tokio::task::spawn_blocking(move || {
+        let fasta =
+            sage_core::fasta::Fasta::parse(contents, &parameters.decoy_tag, parameters.generate_decoys);
+        parameters.build(fasta)
+    })
+    .await
+    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, format!("reload task panicked: {e}")))?;
+
+    let summary = DatabaseSummary {
+        peptides: db.peptides.len(),
+        fragments: db.fragments.len(),
+    };
+
+    state.metrics.db_peptides.set(summary.peptides as i64);
+    state.metrics.db_fragments.set(summary.fragments as i64);
+    state.db.store(Arc::new(db));
+
+    Ok(Json(summary))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn headers_with_key(key: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert("x-api-key", key.parse().unwrap());
+        headers
+    }
+
+    #[test]
+    fn disabled_when_unset() {
+        let err = check_api_key(&HeaderMap::new(), &None).unwrap_err();
+        assert_eq!(err.0, StatusCode::NOT_FOUND);
+    }
+
+    #[test]
+    fn rejects_missing_header() {
+        let configured = Some("secret".to_string());
+        let err = check_api_key(&HeaderMap::new(), &configured).unwrap_err();
+        assert_eq!(err.0, StatusCode::UNAUTHORIZED);
+    }
+
+    #[test]
+    fn rejects_wrong_key() {
+        let configured = Some("secret".to_string());
+        let headers = headers_with_key("wrong");
+        let err = check_api_key(&headers, &configured).unwrap_err();
+        assert_eq!(err.0, StatusCode::UNAUTHORIZED);
+    }
+
+    #[test]
+    fn accepts_matching_key() {
+        let configured = Some("secret".to_string());
+        let headers = headers_with_key("secret");
+        assert!(check_api_key(&headers, &configured).is_ok());
+    }
+}
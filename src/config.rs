@@ -0,0 +1,72 @@
+use serde::Deserialize;
+use std::net::SocketAddr;
+
+fn default_bind_addr() -> SocketAddr {
+    SocketAddr::from(([127, 0, 0, 1], 3000))
+}
+
+fn default_params_path() -> String {
+    "params.json".into()
+}
+
+fn default_report_psms_default() -> usize {
+    1
+}
+
+fn default_enable_compression() -> bool {
+    true
+}
+
+fn default_tracing_filter() -> String {
+    "info".into()
+}
+
+/// Server configuration, layered from an optional config file and
+/// `SAGE_RTS_*` environment overrides so the same binary can run in dev and
+/// production without code edits.
+#[derive(Clone, Deserialize)]
+pub struct ServerConfig {
+    #[serde(default = "default_bind_addr")]
+    pub bind_addr: SocketAddr,
+    #[serde(default = "default_params_path")]
+    pub params_path: String,
+    #[serde(default = "default_report_psms_default")]
+    pub report_psms_default: usize,
+    /// Allowed CORS origins. Empty means "allow any", matching the previous
+    /// `CorsLayer::very_permissive()` behavior.
+    #[serde(default)]
+    pub cors_origins: Vec<String>,
+    #[serde(default = "default_enable_compression")]
+    pub enable_compression: bool,
+    #[serde(default = "default_tracing_filter")]
+    pub tracing_filter: String,
+    /// API key required on `/admin/*` routes. Admin routes are refused
+    /// entirely when this is unset, so hot-reload is opt-in per deployment.
+    #[serde(default)]
+    pub admin_api_key: Option<String>,
+}
+
+impl ServerConfig {
+    /// Load config from (in increasing priority order) built-in defaults, an
+    /// optional config file (path from `$SAGE_RTS_CONFIG`, default
+    /// `sage-rts` with any supported extension such as `.toml`, `.json` or
+    /// `.yaml`), and `SAGE_RTS_*` environment variables, e.g.
+    /// `SAGE_RTS_BIND_ADDR=0.0.0.0:3000`.
+    /// `.separator("__")` below only controls splitting of nested keys
+    /// (`ServerConfig` is flat today, so it has no effect yet); the prefix
+    /// itself is stripped on the single underscore before the field name,
+    /// set explicitly by `.prefix_separator("_")`.
+    pub fn load() -> Result<Self, config::ConfigError> {
+        let path = std::env::var("SAGE_RTS_CONFIG").unwrap_or_else(|_| "sage-rts".into());
+
+        config::Config::builder()
+            .add_source(config::File::with_name(&path).required(false))
+            .add_source(
+                config::Environment::with_prefix("SAGE_RTS")
+                    .prefix_separator("_")
+                    .separator("__"),
+            )
+            .build()?
+            .try_deserialize()
+    }
+}
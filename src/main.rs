@@ -1,22 +1,144 @@
+mod admin;
+mod config;
+mod ingest;
+mod metrics;
+
+use arc_swap::ArcSwap;
 use axum::extract::{Json, State};
-use axum::routing::post;
+use axum::http::HeaderValue;
+use axum::routing::{get, post};
 use axum::Router;
+use config::ServerConfig;
+use metrics::Metrics;
+use prometheus::Registry;
+use rayon::prelude::*;
 use sage_core::database::IndexedDatabase;
 use sage_core::mass::Tolerance;
 use sage_core::scoring::{Feature, Scorer};
 use sage_core::spectrum::{Precursor, RawSpectrum, SpectrumProcessor};
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
-use tower_http::{compression::CompressionLayer, cors::CorsLayer};
+use std::time::Instant;
+use tower_http::{
+    compression::CompressionLayer, cors::CorsLayer, decompression::RequestDecompressionLayer,
+};
 use tracing::info;
 
-#[derive(Deserialize, Serialize)]
-pub struct ScoreRequest {
+/// Shared application state handed to every route: the hot-swappable
+/// database, the metrics registry used to instrument it, and the config it
+/// was built from.
+#[derive(Clone)]
+pub struct AppState {
+    db: Arc<ArcSwap<IndexedDatabase>>,
+    metrics: Arc<Metrics>,
+    registry: Arc<Registry>,
+    config: Arc<ServerConfig>,
+}
+
+/// Build a `CorsLayer` from the configured allow-list, falling back to
+/// `very_permissive()` when no origins are configured (matching the old
+/// hard-coded default for dev convenience).
+fn build_cors_layer(origins: &[String]) -> CorsLayer {
+    if origins.is_empty() {
+        return CorsLayer::very_permissive();
+    }
+
+    let origins: Vec<HeaderValue> = origins
+        .iter()
+        .filter_map(|origin| match origin.parse() {
+            Ok(value) => Some(value),
+            Err(e) => {
+                tracing::warn!("ignoring unparseable cors_origins entry {origin:?}: {e}");
+                None
+            }
+        })
+        .collect();
+
+    CorsLayer::new()
+        .allow_origin(origins)
+        .allow_methods(tower_http::cors::Any)
+        .allow_headers(tower_http::cors::Any)
+}
+
+fn default_min_matched_peaks() -> u8 {
+    4
+}
+
+fn default_max_precursor_charge() -> u8 {
+    6
+}
+
+fn default_min_precursor_charge() -> u8 {
+    1
+}
+
+fn default_max_fragment_charge() -> Option<u8> {
+    Some(1)
+}
+
+fn default_min_fragment_mass() -> f32 {
+    125.0
+}
+
+fn default_max_fragment_mass() -> f32 {
+    2500.0
+}
+
+fn default_take_top_n() -> usize {
+    150
+}
+
+fn default_max_deisotope_mz() -> f32 {
+    2000.0
+}
+
+/// Scorer/processor knobs shared between the single-spectrum and batch
+/// scoring routes. Every field beyond the tolerances has a default matching
+/// the values `score_v1` used to hard-code, so existing clients keep working
+/// unchanged while new ones can tune sensitivity per request.
+#[derive(Clone, Deserialize, Serialize)]
+pub struct ScorerSettings {
     precursor_tolerance: Tolerance,
     fragment_tolerance: Tolerance,
-    report_psms: usize,
+    /// Falls back to the server's `report_psms_default` config when omitted.
+    #[serde(default)]
+    report_psms: Option<usize>,
+    #[serde(default)]
     chimera: bool,
+    #[serde(default)]
     deisotope: bool,
+    #[serde(default)]
+    wide_window: bool,
+
+    #[serde(default = "default_min_matched_peaks")]
+    min_matched_peaks: u8,
+    #[serde(default)]
+    min_isotope_err: i8,
+    #[serde(default)]
+    max_isotope_err: i8,
+    #[serde(default = "default_min_precursor_charge")]
+    min_precursor_charge: u8,
+    #[serde(default = "default_max_precursor_charge")]
+    max_precursor_charge: u8,
+    #[serde(default = "default_max_fragment_charge")]
+    max_fragment_charge: Option<u8>,
+    #[serde(default = "default_min_fragment_mass")]
+    min_fragment_mass: f32,
+    #[serde(default = "default_max_fragment_mass")]
+    max_fragment_mass: f32,
+
+    #[serde(default = "default_take_top_n")]
+    take_top_n: usize,
+    #[serde(default)]
+    min_deisotope_mz: f32,
+    #[serde(default = "default_max_deisotope_mz")]
+    max_deisotope_mz: f32,
+}
+
+#[derive(Deserialize, Serialize)]
+pub struct ScoreRequest {
+    #[serde(flatten)]
+    shared: ScorerSettings,
 
     precursor_mz: f32,
     precursor_charge: u8,
@@ -24,35 +146,83 @@ pub struct ScoreRequest {
     intensity: Vec<f32>,
 }
 
-async fn score_v1(
-    State(db): State<Arc<IndexedDatabase>>,
-    Json(query): Json<ScoreRequest>,
-) -> Result<Json<Vec<Feature>>, (axum::http::StatusCode, String)> {
+/// A single spectrum within a `BatchScoreRequest`, tagged with a caller-supplied
+/// `id` so results can be correlated back to the request that produced them.
+#[derive(Deserialize, Serialize)]
+pub struct SpectrumInput {
+    id: String,
+    precursor_mz: f32,
+    precursor_charge: u8,
+    mz: Vec<f32>,
+    intensity: Vec<f32>,
+}
+
+#[derive(Deserialize, Serialize)]
+pub struct BatchScoreRequest {
+    shared: ScorerSettings,
+    spectra: Vec<SpectrumInput>,
+}
+
+#[derive(Deserialize, Serialize)]
+pub struct BatchResult {
+    id: String,
+    features: Vec<Feature>,
+}
+
+/// Score a single already-assembled `RawSpectrum`. Shared by every route that
+/// produces a `RawSpectrum` from a different source: a JSON body
+/// (`score_v1`/`score_batch_v1`) or a parsed mzML scan (`score_file_v1`).
+fn score_spectrum(
+    db: &IndexedDatabase,
+    shared: &ScorerSettings,
+    default_report_psms: usize,
+    spectrum: RawSpectrum,
+) -> Vec<Feature> {
     let scorer = Scorer {
-        db: &db,
-        precursor_tol: query.precursor_tolerance,
-        fragment_tol: query.fragment_tolerance,
-        min_matched_peaks: 4,
-        min_isotope_err: 0,
-        max_isotope_err: 0,
-        min_precursor_charge: 1,
-        max_precursor_charge: 6,
-        max_fragment_charge: Some(1),
-        min_fragment_mass: 125.0,
-        max_fragment_mass: 2500.0,
-        chimera: false,
-        report_psms: query.report_psms,
-        wide_window: false,
+        db,
+        precursor_tol: shared.precursor_tolerance,
+        fragment_tol: shared.fragment_tolerance,
+        min_matched_peaks: shared.min_matched_peaks,
+        min_isotope_err: shared.min_isotope_err,
+        max_isotope_err: shared.max_isotope_err,
+        min_precursor_charge: shared.min_precursor_charge,
+        max_precursor_charge: shared.max_precursor_charge,
+        max_fragment_charge: shared.max_fragment_charge,
+        min_fragment_mass: shared.min_fragment_mass,
+        max_fragment_mass: shared.max_fragment_mass,
+        chimera: shared.chimera,
+        report_psms: shared.report_psms.unwrap_or(default_report_psms),
+        wide_window: shared.wide_window,
     };
 
-    let spectra = RawSpectrum {
+    let spectrum = SpectrumProcessor::new(
+        shared.take_top_n,
+        shared.min_deisotope_mz,
+        shared.max_deisotope_mz,
+        shared.deisotope,
+    )
+    .process(spectrum);
+
+    scorer.score(&spectrum)
+}
+
+fn score_one(
+    db: &IndexedDatabase,
+    shared: &ScorerSettings,
+    default_report_psms: usize,
+    precursor_mz: f32,
+    precursor_charge: u8,
+    mz: Vec<f32>,
+    intensity: Vec<f32>,
+) -> Vec<Feature> {
+    let spectrum = RawSpectrum {
         file_id: 0,
         ms_level: 2,
         id: "real-time".into(),
         precursors: vec![Precursor {
-            mz: query.precursor_mz,
+            mz: precursor_mz,
             intensity: None,
-            charge: Some(query.precursor_charge),
+            charge: Some(precursor_charge),
             spectrum_ref: None,
             isolation_window: None,
         }],
@@ -60,29 +230,107 @@ async fn score_v1(
         scan_start_time: 0.0,
         ion_injection_time: 0.0,
         total_ion_current: 0.0,
-        mz: query.mz,
-        intensity: query.intensity,
+        mz,
+        intensity,
     };
 
-    let spectra =
-        SpectrumProcessor::new(150, 0.0, 2000.0, query.deisotope).process(spectra.clone());
+    score_spectrum(db, shared, default_report_psms, spectrum)
+}
 
-    let scores = scorer.score(&spectra);
+async fn score_v1(
+    State(state): State<AppState>,
+    Json(query): Json<ScoreRequest>,
+) -> Result<Json<Vec<Feature>>, (axum::http::StatusCode, String)> {
+    let start = Instant::now();
+    let db = state.db.load_full();
+    let scores = score_one(
+        &db,
+        &query.shared,
+        state.config.report_psms_default,
+        query.precursor_mz,
+        query.precursor_charge,
+        query.mz,
+        query.intensity,
+    );
+
+    state.metrics.requests_total.inc();
+    state.metrics.spectra_total.inc();
+    state.metrics.psms_reported.observe(scores.len() as f64);
+    state
+        .metrics
+        .scoring_latency_seconds
+        .observe(start.elapsed().as_secs_f64());
 
     Ok(Json(scores))
 }
 
+/// Score many spectra in one request, amortizing connection and
+/// serialization overhead across a whole run's worth of MS2 scans.
+async fn score_batch_v1(
+    State(state): State<AppState>,
+    Json(query): Json<BatchScoreRequest>,
+) -> Result<Json<Vec<BatchResult>>, (axum::http::StatusCode, String)> {
+    let shared = query.shared;
+    let db = state.db.load_full();
+    let default_report_psms = state.config.report_psms_default;
+    let metrics = state.metrics.clone();
+
+    let results = tokio::task::spawn_blocking(move || {
+        query
+            .spectra
+            .into_par_iter()
+            .map(|spectrum| {
+                let start = Instant::now();
+                let features = score_one(
+                    &db,
+                    &shared,
+                    default_report_psms,
+                    spectrum.precursor_mz,
+                    spectrum.precursor_charge,
+                    spectrum.mz,
+                    spectrum.intensity,
+                );
+                metrics.psms_reported.observe(features.len() as f64);
+                metrics
+                    .scoring_latency_seconds
+                    .observe(start.elapsed().as_secs_f64());
+
+                BatchResult {
+                    features,
+                    id: spectrum.id,
+                }
+            })
+            .collect::<Vec<_>>()
+    })
+    .await
+    .map_err(|e| {
+        (
+            axum::http::StatusCode::INTERNAL_SERVER_ERROR,
+            format!("scoring task panicked: {e}"),
+        )
+    })?;
+
+    state.metrics.requests_total.inc();
+    state.metrics.spectra_total.inc_by(results.len() as u64);
+
+    Ok(Json(results))
+}
+
 pub type Error = Box<dyn std::error::Error + Send + Sync + 'static>;
 
 #[tokio::main]
 async fn main() -> Result<(), Error> {
+    let config = ServerConfig::load()?;
+
     tracing_subscriber::fmt()
         .with_ansi(true)
-        .with_max_level(tracing::Level::TRACE)
+        .with_env_filter(tracing_subscriber::EnvFilter::new(&config.tracing_filter))
         .init();
 
-    let parameters: sage_core::database::Builder =
-        serde_json::from_str(&tokio::fs::read_to_string("params.json").await.unwrap()).unwrap();
+    let parameters: sage_core::database::Builder = serde_json::from_str(
+        &tokio::fs::read_to_string(&config.params_path).await.unwrap(),
+    )
+    .unwrap();
 
     let parameters = parameters.make_parameters();
     let contents = tokio::fs::read_to_string(&parameters.fasta).await.unwrap();
@@ -92,13 +340,35 @@ async fn main() -> Result<(), Error> {
 
     let db = parameters.build(fasta);
 
-    let app = Router::new()
+    let registry = Arc::new(Registry::new());
+    let metrics = Arc::new(Metrics::new(&registry)?);
+    metrics.db_peptides.set(db.peptides.len() as i64);
+    metrics.db_fragments.set(db.fragments.len() as i64);
+
+    let addr = config.bind_addr;
+    let cors = build_cors_layer(&config.cors_origins);
+    let enable_compression = config.enable_compression;
+
+    let state = AppState {
+        db: Arc::new(ArcSwap::from_pointee(db)),
+        metrics,
+        registry,
+        config: Arc::new(config),
+    };
+
+    let mut app = Router::new()
         .route("/v1/score/", post(score_v1))
-        .with_state(Arc::new(db))
-        .layer(CorsLayer::very_permissive())
-        .layer(CompressionLayer::new().gzip(true).deflate(true));
+        .route("/v1/score/batch/", post(score_batch_v1))
+        .route("/v1/score/file/", post(ingest::score_file_v1))
+        .route("/admin/reload", post(admin::reload_v1))
+        .route("/metrics", get(metrics::metrics_v1))
+        .with_state(state)
+        .layer(cors)
+        .layer(RequestDecompressionLayer::new().gzip(true));
 
-    let addr = std::net::SocketAddr::from(([127, 0, 0, 1], 3000));
+    if enable_compression {
+        app = app.layer(CompressionLayer::new().gzip(true).deflate(true));
+    }
 
     axum::Server::bind(&addr)
         .serve(app.into_make_service())
@@ -0,0 +1,79 @@
+use axum::extract::State;
+use axum::http::header;
+use axum::response::{IntoResponse, Response};
+use prometheus::{Encoder, Histogram, HistogramOpts, IntCounter, IntGauge, Registry, TextEncoder};
+
+use crate::AppState;
+
+/// Collectors registered against the server's `Arc<Registry>`, threaded
+/// through `State` alongside the `IndexedDatabase`.
+pub struct Metrics {
+    pub scoring_latency_seconds: Histogram,
+    pub requests_total: IntCounter,
+    pub spectra_total: IntCounter,
+    pub psms_reported: Histogram,
+    pub db_peptides: IntGauge,
+    pub db_fragments: IntGauge,
+}
+
+impl Metrics {
+    pub fn new(registry: &Registry) -> prometheus::Result<Self> {
+        let scoring_latency_seconds = Histogram::with_opts(HistogramOpts::new(
+            "sage_rts_scoring_latency_seconds",
+            "End-to-end latency of scoring a single spectrum",
+        ))?;
+        let requests_total = IntCounter::new(
+            "sage_rts_requests_total",
+            "Number of scoring requests handled",
+        )?;
+        let spectra_total = IntCounter::new(
+            "sage_rts_spectra_total",
+            "Number of spectra scored across all requests",
+        )?;
+        // Not the candidate-peptides-examined metric originally requested:
+        // Scorer::score (sage_core) only returns the already-truncated
+        // Vec<Feature>, not the pre-truncation search-space size, so that
+        // metric needs an upstream sage_core change to expose a candidate
+        // count and is out of scope here.
+        let psms_reported = Histogram::with_opts(HistogramOpts::new(
+            "sage_rts_psms_reported",
+            "Number of PSMs returned per query (bounded by report_psms)",
+        ))?;
+        let db_peptides = IntGauge::new(
+            "sage_rts_db_peptides",
+            "Number of peptides in the loaded IndexedDatabase",
+        )?;
+        let db_fragments = IntGauge::new(
+            "sage_rts_db_fragments",
+            "Number of fragments in the loaded IndexedDatabase",
+        )?;
+
+        registry.register(Box::new(scoring_latency_seconds.clone()))?;
+        registry.register(Box::new(requests_total.clone()))?;
+        registry.register(Box::new(spectra_total.clone()))?;
+        registry.register(Box::new(psms_reported.clone()))?;
+        registry.register(Box::new(db_peptides.clone()))?;
+        registry.register(Box::new(db_fragments.clone()))?;
+
+        Ok(Metrics {
+            scoring_latency_seconds,
+            requests_total,
+            spectra_total,
+            psms_reported,
+            db_peptides,
+            db_fragments,
+        })
+    }
+}
+
+pub async fn metrics_v1(State(state): State<AppState>) -> Response {
+    let encoder = TextEncoder::new();
+    let metric_families = state.registry.gather();
+    let mut buffer = Vec::new();
+    if let Err(e) = encoder.encode(&metric_families, &mut buffer) {
+        tracing::error!("failed to encode metrics: {e}");
+        return (axum::http::StatusCode::INTERNAL_SERVER_ERROR, "").into_response();
+    }
+
+    ([(header::CONTENT_TYPE, encoder.format_type().to_string())], buffer).into_response()
+}